@@ -3,19 +3,34 @@
 
 //! This file contains functions related to codegenning MIR functions into gotoc
 
-use crate::context::metadata::HarnessMetadata;
+use crate::context::metadata::{
+    ContractMetadata, HarnessMetadata, KaniMetadata, NondetSite,
+};
 use crate::GotocCtx;
 use cbmc::goto_program::{Expr, Stmt, Symbol};
 use cbmc::InternString;
 use rustc_ast::ast;
 use rustc_ast::{Attribute, LitKind};
-use rustc_middle::mir::{HasLocalDecls, Local};
+use rustc_middle::mir::{HasLocalDecls, Local, TerminatorKind};
 use rustc_middle::ty::{self, Instance};
 use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::iter::FromIterator;
 use tracing::{debug, warn};
 
+/// Kanitool attribute name tagging the precondition/postcondition checking copy
+/// of a function under contract.
+const CONTRACT_CHECK: &str = "checked_with";
+/// Kanitool attribute name tagging the contract-as-stub copy of a function under
+/// contract, used to replace a callee with its contract during modular proofs.
+const CONTRACT_REPLACE: &str = "replace";
+/// Kanitool attribute name tagging the copy used when a function under contract
+/// calls itself recursively.
+const CONTRACT_RECURSION: &str = "recursion_check";
+/// Kanitool attribute name marking a harness that verifies a single function
+/// against its contract rather than inlining its callees.
+const PROOF_FOR_CONTRACT: &str = "proof_for_contract";
+
 /// Utility to skip functions that can't currently be successfully codgenned.
 impl<'tcx> GotocCtx<'tcx> {
     fn should_skip_current_fn(&self) -> bool {
@@ -35,6 +50,14 @@ impl<'tcx> GotocCtx<'tcx> {
             _ => false,
         }
     }
+
+    /// If the active harness registered a `#[kani::stub(original, replacement)]`
+    /// whose `original` matches the function currently being codegenned, resolve
+    /// and return the replacement `Instance`.
+    fn stub_for_current_fn(&self) -> Option<Instance<'tcx>> {
+        let replacement = self.stubs.get(self.current_fn().readable_name())?;
+        self.resolve_instance_by_name(replacement)
+    }
 }
 
 /// Codegen MIR functions into gotoc
@@ -74,15 +97,19 @@ impl<'tcx> GotocCtx<'tcx> {
         let old_sym = self.symbol_table.lookup(&name).unwrap();
         if old_sym.is_function_definition() {
             warn!("Double codegen of {:?}", old_sym);
+        } else if let Some(replacement) = self.stub_for_current_fn() {
+            // A `#[kani::stub(original, replacement)]` on the active harness asks
+            // us to codegen the replacement's body into the original's symbol
+            // instead of the original definition (or the hardcoded skip path).
+            debug!(
+                "Stubbing function {} with {}",
+                self.current_fn().readable_name(),
+                self.readable_instance_name(replacement)
+            );
+            self.codegen_stub(instance, replacement);
         } else if self.should_skip_current_fn() {
             debug!("Skipping function {}", self.current_fn().readable_name());
-            let body = self.codegen_fatal_error(
-                &GotocCtx::unsupported_msg(
-                    &(String::from("The function ") + self.current_fn().readable_name()),
-                    None,
-                ),
-                Some(self.current_fn().mir().span),
-            );
+            let body = self.codegen_skipped_fn_body();
             self.symbol_table.update_fn_declaration_with_definition(&name, body);
         } else {
             assert!(old_sym.is_function());
@@ -96,6 +123,15 @@ impl<'tcx> GotocCtx<'tcx> {
             let loc = self.codegen_span(&mir.span);
             let stmts = self.current_fn_mut().extract_block();
             let body = Stmt::block(stmts, loc);
+            // Record this function's contract (if any) *before* attempting the
+            // swap, so `substitute_contract_variant` can find it in
+            // `self.contract_info`.
+            self.record_contract_metadata();
+            // If this function is under contract and the active harness is a
+            // `proof_for_contract` target, keep only the relevant expanded
+            // variant (the precondition/postcondition check or the
+            // contract-as-stub replacement) and prune the remaining behaviors.
+            let body = self.substitute_contract_variant(body);
             self.symbol_table.update_fn_declaration_with_definition(&name, body);
 
             self.handle_kanitool_attributes();
@@ -103,6 +139,83 @@ impl<'tcx> GotocCtx<'tcx> {
         self.reset_current_fn();
     }
 
+    /// Build the body we splice in for a function we can't codegen (see
+    /// [`should_skip_current_fn`](Self::should_skip_current_fn)).
+    ///
+    /// We always emit the unconditional `codegen_fatal_error`: because CBMC only
+    /// flags reachable assertions, it passes when the call is unreachable and
+    /// fails when a path actually reaches the unsupported construct, which is the
+    /// sound baseline behavior. When the reachability-aware mode is enabled (via
+    /// `--assertion-reach-checks`) we additionally *wrap* that assertion with the
+    /// same reachability cover used for coverage reporting, so the two cases are
+    /// distinguishable in the report: a reached construct shows the cover as
+    /// satisfied alongside the failing assertion, while an unreachable call leaves the cover unsatisfiable and
+    /// the proof still succeeds.
+    fn codegen_skipped_fn_body(&mut self) -> Stmt {
+        let span = self.current_fn().mir().span;
+        let msg = GotocCtx::unsupported_msg(
+            &(String::from("The function ") + self.current_fn().readable_name()),
+            None,
+        );
+        let fatal = self.codegen_fatal_error(&msg, Some(span));
+        if self.reachability_check_unsupported {
+            // Keep the failing assertion (soundness) and add a cover so a reached
+            // construct is additionally reported as reachable.
+            let cover = Stmt::cover(Expr::bool_true(), self.codegen_span(&span));
+            Stmt::block(vec![cover, fatal], self.codegen_span(&span))
+        } else {
+            fatal
+        }
+    }
+
+    /// Codegen the body of `replacement` into the symbol of `original`.
+    ///
+    /// This backs `#[kani::stub(original, replacement)]`: the two signatures must
+    /// be compatible (same monomorphized argument and return types) so that every call site
+    /// of `original` type-checks against the replacement body we splice in.
+    fn codegen_stub(&mut self, original: Instance<'tcx>, replacement: Instance<'tcx>) {
+        // Symbol we will write the replacement definition into.
+        let name = self.current_fn().name();
+        // Compare the monomorphized argument and return types rather than the
+        // whole `Binder<FnSig>`: we don't care about lifetime/region differences,
+        // and `fn_sig` would ICE on the closures/generators this mechanism is
+        // meant to override (e.g. `Semaphore::acquire_owned::{closure#0}`).
+        let (original_inputs, original_output) = self.instance_args_and_ret(original);
+        let (replacement_inputs, replacement_output) = self.instance_args_and_ret(replacement);
+        if original_inputs != replacement_inputs || original_output != replacement_output {
+            self.tcx.sess.span_err(
+                self.current_fn().mir().span,
+                &format!(
+                    "Stub `{}` is not signature-compatible with `{}`",
+                    self.readable_instance_name(replacement),
+                    self.current_fn().readable_name()
+                ),
+            );
+            return;
+        }
+
+        // Codegen the replacement exactly as we would any other function, but
+        // write the resulting definition into the original's symbol. `original`
+        // is the active `current_fn` on entry, so reset it before switching to
+        // `replacement` to keep the strict set/reset pairing the rest of this
+        // file relies on (`set_current_fn` asserts nothing is already set), then
+        // restore `original` on the way out for `codegen_function`'s trailing
+        // `reset_current_fn`.
+        self.reset_current_fn();
+        self.set_current_fn(replacement);
+        self.print_instance(replacement, self.current_fn().mir());
+        self.codegen_function_prelude();
+        self.codegen_declare_variables();
+        let mir = self.current_fn().mir();
+        mir.basic_blocks().iter_enumerated().for_each(|(bb, bbd)| self.codegen_block(bb, bbd));
+        let loc = self.codegen_span(&mir.span);
+        let stmts = self.current_fn_mut().extract_block();
+        let body = Stmt::block(stmts, loc);
+        self.symbol_table.update_fn_declaration_with_definition(&name, body);
+        self.reset_current_fn();
+        self.set_current_fn(original);
+    }
+
     /// MIR functions have a `spread_arg` field that specifies whether the
     /// final argument to the function is "spread" at the LLVM/codegen level
     /// from a tuple into its individual components. (Used for the "rust-
@@ -221,9 +334,50 @@ impl<'tcx> GotocCtx<'tcx> {
         );
     }
 
+    /// The readable (source-level) name of an arbitrary instance, used for
+    /// diagnostics and for matching stub targets supplied by the user.
+    fn readable_instance_name(&self, instance: Instance<'tcx>) -> String {
+        self.tcx.def_path_str(instance.def_id())
+    }
+
+    /// Resolve a user-supplied readable function name (as given to
+    /// `#[kani::stub(..)]`) back to the monomorphized `Instance` we recorded
+    /// while declaring functions.
+    fn resolve_instance_by_name(&self, name: &str) -> Option<Instance<'tcx>> {
+        self.instances_by_readable_name.get(name).copied()
+    }
+
+    /// The monomorphized argument types and return type of an instance.
+    ///
+    /// Unlike `ty.fn_sig`, this copes with closures and generators (whose
+    /// signature lives in their substitutions) so it can be used to compare the
+    /// shape of a stub against the function it replaces.
+    fn instance_args_and_ret(
+        &self,
+        instance: Instance<'tcx>,
+    ) -> (Vec<ty::Ty<'tcx>>, ty::Ty<'tcx>) {
+        let fn_ty = self.monomorphize(instance.ty(self.tcx, ty::ParamEnv::reveal_all()));
+        let sig = match fn_ty.kind() {
+            ty::Closure(_, substs) => substs.as_closure().sig(),
+            ty::Generator(_, substs, _) => {
+                // Model a generator as its resume-argument/return pair.
+                let gen = substs.as_generator();
+                let sig = gen.poly_sig().skip_binder();
+                return (vec![sig.resume_ty], sig.return_ty);
+            }
+            _ => fn_ty.fn_sig(self.tcx),
+        };
+        let sig = self.tcx.normalize_erasing_late_bound_regions(ty::ParamEnv::reveal_all(), sig);
+        (sig.inputs().to_vec(), sig.output())
+    }
+
     pub fn declare_function(&mut self, instance: Instance<'tcx>) {
         debug!("declaring {}; {:?}", instance, instance);
         self.set_current_fn(instance);
+        // Remember the instance by its readable name so stub targets can be
+        // resolved later without re-walking the crate.
+        self.instances_by_readable_name
+            .insert(self.current_fn().readable_name().to_owned(), instance);
         self.ensure(&self.current_fn().name(), |ctx, fname| {
             let mir = ctx.current_fn().mir();
             Symbol::function(
@@ -237,6 +391,19 @@ impl<'tcx> GotocCtx<'tcx> {
         self.reset_current_fn();
     }
 
+    /// Assemble the crate-level inventory to be serialized to the metadata JSON.
+    ///
+    /// Besides the proof harnesses, this surfaces the separately-accumulated
+    /// `contract_info` (functions carrying contract attributes, whether or not
+    /// they have a harness) so the driver's `list` subcommand can see them. The
+    /// driver calls this after codegen and writes the result to disk.
+    pub fn kani_metadata(&self) -> KaniMetadata {
+        KaniMetadata {
+            proof_harnesses: self.proof_harnesses.clone(),
+            contract_functions: self.contract_info.values().cloned().collect(),
+        }
+    }
+
     /// This updates the goto context with any information that should be accumulated from a function's
     /// attributes.
     ///
@@ -249,23 +416,35 @@ impl<'tcx> GotocCtx<'tcx> {
         // TODO: This can be modifed to use Enums when more options are provided
         let mut attribute_vector = vec![];
         let mut proof_attribute_vector = vec![];
+        // Contract attributes tag the expanded copies of a function-under-contract's
+        // body (`checked_with`, `replace`, `recursion_check`). They live on the
+        // function itself, not on the proof harness.
+        let mut contract_attribute_vector = vec![];
 
         // Loop through instances to get all "kanitool::x" attribute strings
         for attr in self.tcx.get_attrs(instance.def_id()) {
             // Get the string the appears after "kanitool::" in each attribute string.
             // Ex - "proof" | "unwind" etc.
             if let Some(attribute_string) = kanitool_attr_name(attr).as_deref() {
-                // Push to proof vector
-                if attribute_string == "proof" {
-                    proof_attribute_vector.push(attr);
-                }
-                // Push to attribute vector that can be expanded to a map when more options become available
-                else {
-                    attribute_vector.push((attribute_string.to_string(), attr));
+                match attribute_string {
+                    // Push to proof vector
+                    "proof" => proof_attribute_vector.push(attr),
+                    // Contract behaviors are collected separately so they can be
+                    // recorded even when the function carries no proof harness.
+                    CONTRACT_CHECK | CONTRACT_REPLACE | CONTRACT_RECURSION => {
+                        contract_attribute_vector.push((attribute_string.to_string(), attr))
+                    }
+                    // Push to attribute vector that can be expanded to a map when more options become available
+                    _ => attribute_vector.push((attribute_string.to_string(), attr)),
                 }
             }
         }
 
+        // The function's contract (if any) is recorded earlier in
+        // `codegen_function`, before body substitution. Here we only need to know
+        // whether contract attributes are present so a contract-only function is
+        // exempt from the "missing #[kani::proof]" diagnostic below.
+
         // In the case when there's only one proof attribute (correct behavior), create harness and modify it
         // depending on each subsequent attribute that's being called by the user.
         if proof_attribute_vector.len() == 1 {
@@ -273,15 +452,32 @@ impl<'tcx> GotocCtx<'tcx> {
             if attribute_vector.len() > 0 {
                 // loop through all subsequent attributes
                 for attribute_tuple in attribute_vector.iter() {
-                    // match with "unwind" attribute and provide the harness for modification
                     match attribute_tuple.0.as_str() {
+                        // match with "unwind" attribute and provide the harness for modification
                         "unwind" => {
                             self.handle_kanitool_unwind(attribute_tuple.1, &mut harness_metadata)
                         }
+                        // A `proof_for_contract(target)` harness verifies a single
+                        // function under its contract rather than inlining callees.
+                        PROOF_FOR_CONTRACT => self
+                            .handle_kanitool_proof_for_contract(
+                                attribute_tuple.1,
+                                &mut harness_metadata,
+                            ),
+                        // `#[kani::stub(original, replacement)]` overrides a
+                        // function's body for this harness only.
+                        "stub" => {
+                            self.handle_kanitool_stub(attribute_tuple.1, &mut harness_metadata)
+                        }
                         _ => {}
                     }
                 }
             }
+            // A harness that targets a contract or applies stubs replays
+            // under modified semantics, so mark it: the driver annotates the
+            // generated test as a potentially-unsound replay.
+            harness_metadata.is_contract_stub_harness = harness_metadata.proof_for_contract.is_some()
+                || !harness_metadata.stubs.is_empty();
             // self.proof_harnesses contains the final metadata that's to be parsed
             self.proof_harnesses.push(harness_metadata);
         }
@@ -291,8 +487,13 @@ impl<'tcx> GotocCtx<'tcx> {
                 .sess
                 .span_err(proof_attribute_vector[0].span, "Only one Proof Attribute allowed");
         }
-        // User error handling for when there's an attribute being called without #kani::tool
-        else if proof_attribute_vector.len() == 0 && attribute_vector.len() > 0 {
+        // User error handling for when there's an attribute being called without #kani::tool.
+        // Contract attributes are exempt: they legitimately appear on functions that have no
+        // proof harness of their own.
+        else if proof_attribute_vector.len() == 0
+            && attribute_vector.len() > 0
+            && contract_attribute_vector.is_empty()
+        {
             self.tcx.sess.span_err(
                 attribute_vector[0].1.span,
                 "Please use '#kani[proof]' above the annotation",
@@ -301,8 +502,212 @@ impl<'tcx> GotocCtx<'tcx> {
         }
     }
 
+    /// Record the expanded-variant mangled names for a function under contract.
+    ///
+    /// The proc-macro expands the annotated body once per behavior and tags each
+    /// copy with a `checked_with`/`replace`/`recursion_check` kanitool attribute
+    /// carrying the mangled name of the generated closure. We stash these in
+    /// `self.contract_info`, keyed by the function's readable name, so that
+    /// `substitute_contract_variant` can pick the right one when a harness
+    /// targets this contract.
+    fn record_contract_metadata(&mut self) -> bool {
+        let instance = self.current_fn().instance();
+        let contract_attrs: Vec<_> = self
+            .tcx
+            .get_attrs(instance.def_id())
+            .iter()
+            .filter_map(|attr| match kanitool_attr_name(attr).as_deref() {
+                Some(CONTRACT_CHECK) => Some((CONTRACT_CHECK, attr)),
+                Some(CONTRACT_REPLACE) => Some((CONTRACT_REPLACE, attr)),
+                Some(CONTRACT_RECURSION) => Some((CONTRACT_RECURSION, attr)),
+                _ => None,
+            })
+            .collect();
+        if contract_attrs.is_empty() {
+            return false;
+        }
+
+        let current_fn = self.current_fn();
+        let loc = self.codegen_span(&current_fn.mir().span);
+        let mut contract = ContractMetadata {
+            pretty_name: current_fn.readable_name().to_owned(),
+            mangled_name: current_fn.name(),
+            // Recorded so the `list` subcommand can group contract functions by
+            // source file alongside the proof harnesses, even when the function
+            // carries no harness of its own.
+            original_file: loc.filename().unwrap(),
+            original_line: loc.line().unwrap().to_string(),
+            checked_with: None,
+            replace_with: None,
+            recursion_check: None,
+        };
+        for (name, attr) in contract_attrs {
+            let target = match extract_string_argument(attr) {
+                Some(mangled) => mangled,
+                None => {
+                    self.tcx.sess.span_err(
+                        attr.span,
+                        "Contract attribute expects exactly one mangled-name string argument",
+                    );
+                    continue;
+                }
+            };
+            match name {
+                CONTRACT_CHECK => contract.checked_with = Some(target),
+                CONTRACT_REPLACE => contract.replace_with = Some(target),
+                CONTRACT_RECURSION => contract.recursion_check = Some(target),
+                _ => unreachable!("unexpected contract attribute {name}"),
+            }
+        }
+        self.contract_info.insert(contract.pretty_name.clone(), contract);
+        true
+    }
+
+    /// Set the function-under-contract target for the current codegen session.
+    ///
+    /// Kani codegens the whole crate once per harness; the driver calls this once
+    /// before that codegen begins (and leaves it unset for ordinary harnesses) so
+    /// `substitute_contract_variant` applies the swap deterministically to the
+    /// function under contract regardless of codegen order.
+    pub fn set_proof_for_contract(&mut self, target: Option<String>) {
+        self.proof_for_contract = target;
+    }
+
+    /// Parse a `proof_for_contract("<fn>")` attribute and record the targeted
+    /// function's readable name on the harness so the driver can re-invoke
+    /// codegen for that harness with [`set_proof_for_contract`](Self::set_proof_for_contract).
+    fn handle_kanitool_proof_for_contract(
+        &mut self,
+        attr: &Attribute,
+        harness: &mut HarnessMetadata,
+    ) {
+        if let Some(target) = extract_string_argument(attr) {
+            // Only record the target on the harness metadata. We deliberately do
+            // *not* mutate `self.proof_for_contract` here: that field is a
+            // whole-crate codegen input, fixed for the duration of one harness'
+            // codegen session (Kani codegens the crate once per harness), so
+            // `substitute_contract_variant` sees a constant value regardless of
+            // the order functions are codegenned in. Setting it from the
+            // harness' own attributes — which are processed at the *end* of the
+            // harness' `codegen_function`, after the function under contract has
+            // already been built — would both miss the swap and leak the target
+            // into every later contract function.
+            harness.proof_for_contract = Some(target);
+        } else {
+            self.tcx
+                .sess
+                .span_err(attr.span, "Exactly one contract target as a string argument accepted");
+        }
+    }
+
+    /// When the active harness is a `proof_for_contract` target and the current
+    /// function is the one under contract, replace its inlined body with the
+    /// expanded `checked_with` (or `replace`, for a callee being stubbed by its
+    /// contract) variant. Otherwise the original body is returned unchanged.
+    fn substitute_contract_variant(&self, body: Stmt) -> Stmt {
+        let target = match &self.proof_for_contract {
+            Some(target) => target,
+            None => return body,
+        };
+        let readable = self.current_fn().readable_name();
+        let contract = match self.contract_info.get(readable) {
+            Some(contract) => contract,
+            None => return body,
+        };
+        // The function directly under proof is checked against its contract; any
+        // other function under contract reached from the harness is replaced by
+        // its contract-as-stub variant (assume-guarantee reasoning).
+        let variant = if target == readable {
+            contract.checked_with.as_ref()
+        } else {
+            contract.replace_with.as_ref()
+        };
+        match variant.and_then(|mangled| self.symbol_table.lookup(mangled)) {
+            Some(variant_sym) if variant_sym.is_function_definition() => variant_sym.body().clone(),
+            _ => body,
+        }
+    }
+
+    /// Record a nondeterministic-value (`kani::any()`) site encountered while
+    /// codegenning the current harness body.
+    ///
+    /// The sites are kept in source order so the driver can line them up against
+    /// CBMC's trace assignments during concrete playback. Fixed-length arrays get
+    /// an `any_raw_array` marker so they are reconstructed element-by-element
+    /// rather than from a single opaque byte blob, which would otherwise produce
+    /// uncompilable or incorrect playback tests.
+    pub fn record_nondet_site(&mut self, ty: ty::Ty<'tcx>, span: Option<rustc_span::Span>) {
+        let ty = self.monomorphize(ty);
+        let loc = self.codegen_span_option(span);
+        self.nondet_sites.push(NondetSite {
+            pretty_type: ty.to_string(),
+            original_file: loc.filename().unwrap_or_default(),
+            original_line: loc.line().map(|l| l.to_string()).unwrap_or_default(),
+            // Fixed-length arrays must be rebuilt element-by-element during
+            // playback; flag them so the driver emits an `any_raw_array`-style
+            // reconstruction rather than a single opaque byte blob.
+            any_raw_array: matches!(ty.kind(), ty::Array(..)),
+        });
+    }
+
+    /// Walk the current harness' MIR and record every nondet-constructor call
+    /// site, in source order, so the harness metadata carries the
+    /// nondeterministic-value inventory the driver needs for concrete playback.
+    ///
+    /// KNOWN LIMITATIONS (to be lifted as the feature matures):
+    /// - Only the harness' *own* MIR is scanned. `kani::any()` calls factored
+    ///   into helper functions reachable from the harness are not recorded, so
+    ///   the trace mapping is incomplete for harnesses that construct nondet
+    ///   values in callees.
+    /// - Callees are matched against the known `kani` nondet constructors by
+    ///   path (`is_nondet_constructor`); re-exports or user aliases that resolve
+    ///   to a different `def_path_str` are not recognized.
+    fn collect_nondet_sites(&mut self) {
+        let mir = self.current_fn().mir();
+        let sites: Vec<(ty::Ty<'tcx>, rustc_span::Span)> = mir
+            .basic_blocks()
+            .iter()
+            .filter_map(|bbd| match &bbd.terminator().kind {
+                TerminatorKind::Call { func, destination, fn_span, .. } => {
+                    let func_ty = func.ty(mir, self.tcx);
+                    let def_id = match func_ty.kind() {
+                        ty::FnDef(def_id, _) => *def_id,
+                        _ => return None,
+                    };
+                    if self.is_nondet_constructor(def_id) {
+                        destination.map(|(place, _)| (place.ty(mir, self.tcx).ty, *fn_span))
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        for (ty, span) in sites {
+            self.record_nondet_site(ty, Some(span));
+        }
+    }
+
+    /// Whether `def_id` is one of Kani's nondeterministic-value constructors
+    /// (`kani::any`, `kani::any_raw`, `kani::any_raw_array`, ...). Matches by
+    /// readable path, so re-exports/aliases resolving to a different path are
+    /// not (yet) recognized.
+    fn is_nondet_constructor(&self, def_id: rustc_hir::def_id::DefId) -> bool {
+        let path = self.tcx.def_path_str(def_id);
+        matches!(path.strip_prefix("kani::"), Some(rest) if rest.starts_with("any"))
+    }
+
+    /// Drain the nondeterministic-value sites accumulated for the harness
+    /// currently being codegenned, resetting the buffer for the next harness.
+    fn take_nondet_sites(&mut self) -> Vec<NondetSite> {
+        std::mem::take(&mut self.nondet_sites)
+    }
+
     /// Update `self` (the goto context) to add the current function as a listed proof harness
     fn handle_kanitool_proof(&mut self) -> HarnessMetadata {
+        // Populate the nondeterministic-value inventory for this harness before
+        // snapshotting it into the metadata below.
+        self.collect_nondet_sites();
         let current_fn = self.current_fn();
         let pretty_name = current_fn.readable_name().to_owned();
         let mangled_name = current_fn.name();
@@ -314,11 +719,40 @@ impl<'tcx> GotocCtx<'tcx> {
             original_file: loc.filename().unwrap(),
             original_line: loc.line().unwrap().to_string(),
             unwind_value: None,
+            proof_for_contract: None,
+            stubs: BTreeMap::new(),
+            // Ordered nondeterministic-value sites recorded while codegenning
+            // this harness' body, consumed here so the driver can map CBMC trace
+            // assignments onto `kani::any()` constructor calls during playback.
+            nondet_sites: self.take_nondet_sites(),
+            is_contract_stub_harness: false,
         };
 
         harness
     }
 
+    /// Parse a `stub(original, replacement)` attribute and record the mapping on
+    /// the harness metadata.
+    ///
+    /// The mapping is deliberately *not* written into the crate-global
+    /// `self.stubs` here: doing so would leak one harness' stubs into every other
+    /// harness that shares the symbol table. Instead the driver replays each
+    /// harness' `HarnessMetadata::stubs` into `self.stubs` before that harness is
+    /// codegenned, so stubbing stays scoped per proof.
+    fn handle_kanitool_stub(&mut self, attr: &Attribute, harness: &mut HarnessMetadata) {
+        match extract_stub_arguments(attr) {
+            Some((original, replacement)) => {
+                harness.stubs.insert(original, replacement);
+            }
+            None => {
+                self.tcx.sess.span_err(
+                    attr.span,
+                    "Exactly two arguments (original, replacement) as strings accepted",
+                );
+            }
+        }
+    }
+
     /// Unwind strings of the format 'unwind(x)' and the mangled names are to be parsed for the value 'x'
     fn handle_kanitool_unwind(&mut self, attr: &Attribute, harness: &mut HarnessMetadata) {
         // Check if some unwind value doesnt already exist
@@ -360,6 +794,48 @@ fn kanitool_attr_name(attr: &ast::Attribute) -> Option<String> {
     }
 }
 
+/// Extracts a single string-literal argument from the provided attribute.
+///
+/// Used for attributes such as `checked_with = "<mangled>"` or
+/// `proof_for_contract("<fn>")` whose payload is the mangled/readable name of
+/// another item.
+fn extract_string_argument(attr: &Attribute) -> Option<String> {
+    // Name-value form, e.g. `checked_with = "<mangled>"`.
+    if let Some(value) = attr.value_str() {
+        return Some(value.to_string());
+    }
+    let attr_args = attr.meta_item_list().map(|x| x.to_vec())?;
+    // Only accept attributes with a single string value as argument.
+    if attr_args.len() == 1 {
+        let x = attr_args[0].literal()?;
+        match x.kind {
+            LitKind::Str(symbol, ..) => Some(symbol.to_string()),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Extracts the two string arguments (`original`, `replacement`) from a
+/// `#[kani::stub(..)]` attribute.
+fn extract_stub_arguments(attr: &Attribute) -> Option<(String, String)> {
+    let attr_args = attr.meta_item_list().map(|x| x.to_vec())?;
+    if attr_args.len() == 2 {
+        let original = match attr_args[0].literal()?.kind {
+            LitKind::Str(symbol, ..) => symbol.to_string(),
+            _ => return None,
+        };
+        let replacement = match attr_args[1].literal()?.kind {
+            LitKind::Str(symbol, ..) => symbol.to_string(),
+            _ => return None,
+        };
+        Some((original, replacement))
+    } else {
+        None
+    }
+}
+
 /// Extracts the integer value argument from the any attribute provided
 fn extract_integer_argument(attr: &Attribute) -> Option<u128> {
     // Vector of meta items , that contain metadata about the annotation
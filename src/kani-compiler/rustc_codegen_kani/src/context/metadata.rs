@@ -0,0 +1,90 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Structured metadata that codegen accumulates about a crate's proof harnesses
+//! and function contracts. It is serialized to the metadata JSON consumed by the
+//! driver, so the driver can enumerate harnesses (and, in a future `list`
+//! subcommand, contracts) without re-running codegen.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Metadata for a single `#[kani::proof]` harness.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarnessMetadata {
+    /// The name the user gave the harness (e.g. `mymod::check_foo`).
+    pub pretty_name: String,
+    /// The symbol-table name of the harness.
+    pub mangled_name: String,
+    /// The file the harness is defined in.
+    pub original_file: String,
+    /// The line the harness is defined on, as a string.
+    pub original_line: String,
+    /// Optional `#[kani::unwind(n)]` value for the harness.
+    pub unwind_value: Option<u32>,
+    /// For a `#[kani::proof_for_contract(f)]` harness, the readable name of the
+    /// function `f` being verified against its contract.
+    pub proof_for_contract: Option<String>,
+    /// `#[kani::stub(original, replacement)]` mappings that apply to this harness
+    /// only, as original -> replacement readable names.
+    pub stubs: StubMap,
+    /// Ordered `kani::any()` call sites in the harness, so the driver can map
+    /// CBMC trace assignments onto constructor calls for concrete playback.
+    pub nondet_sites: Vec<NondetSite>,
+    /// Whether the harness targets a contract or applies stubs, in which case a
+    /// synthesized replay test must be annotated as a potentially-unsound replay.
+    pub is_contract_stub_harness: bool,
+}
+
+/// A single nondeterministic-value (`kani::any()`) site in a harness.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NondetSite {
+    /// The monomorphized type produced at this site.
+    pub pretty_type: String,
+    /// The file the site appears in.
+    pub original_file: String,
+    /// The line the site appears on, as a string.
+    pub original_line: String,
+    /// Set for fixed-length arrays, so the driver reconstructs them
+    /// element-by-element rather than from a single opaque byte blob.
+    pub any_raw_array: bool,
+}
+
+/// Metadata for a function that carries contract attributes (`requires` /
+/// `ensures`). Recorded even when the function has no proof harness of its own so
+/// the `list` subcommand can report it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContractMetadata {
+    /// The readable name of the function under contract.
+    pub pretty_name: String,
+    /// The symbol-table name of the function under contract.
+    pub mangled_name: String,
+    /// The file the function is defined in.
+    pub original_file: String,
+    /// The line the function is defined on, as a string.
+    pub original_line: String,
+    /// Mangled name of the precondition/postcondition checking variant.
+    pub checked_with: Option<String>,
+    /// Mangled name of the contract-as-stub variant.
+    pub replace_with: Option<String>,
+    /// Mangled name of the variant used for recursive calls.
+    pub recursion_check: Option<String>,
+}
+
+/// Mapping of original readable name -> replacement readable name, kept so the
+/// driver can restore a harness' stubs before codegenning it.
+pub type StubMap = BTreeMap<String, String>;
+
+/// The crate-level inventory serialized to the metadata JSON the driver reads.
+///
+/// It carries both the proof harnesses and every function that carries contract
+/// attributes (even those without a harness of their own), so a future `list`
+/// subcommand can group harnesses by source file and report contract/stub counts
+/// without re-running codegen.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct KaniMetadata {
+    /// Every `#[kani::proof]` harness found in the crate.
+    pub proof_harnesses: Vec<HarnessMetadata>,
+    /// Every function carrying contract attributes, harness or not.
+    pub contract_functions: Vec<ContractMetadata>,
+}
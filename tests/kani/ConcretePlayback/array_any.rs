@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --enable-unstable --concrete-playback=print
+// kani-verify-fail
+
+// Check that a harness mixing a scalar and an array `kani::any()` records its
+// nondeterministic-value sites so a concrete-playback test can be synthesized.
+// The array site must be reconstructed element-by-element.
+
+#[kani::proof]
+fn check_array() {
+    let x: u8 = kani::any();
+    let arr: [u8; 4] = kani::any();
+    assert!(x as u32 + arr[0] as u32 != 0);
+}
@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that a `#[kani::proof_for_contract]` harness verifies the function
+// against its contract rather than inlining its callees.
+
+#[kani::requires(x < i32::MAX)]
+#[kani::ensures(result > x)]
+fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+#[kani::proof_for_contract(add_one)]
+fn add_one_contract() {
+    let x: i32 = kani::any();
+    kani::assume(x < i32::MAX);
+    add_one(x);
+}
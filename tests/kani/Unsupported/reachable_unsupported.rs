@@ -0,0 +1,26 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --assertion-reach-checks --output-format regular --no-default-checks
+// kani-verify-fail
+
+// Companion to `unreachable_unsupported.rs`: here the unsupported construct is
+// genuinely reachable (`x + y == 5` holds for `x=0, y=5`), so the harness must
+// fail on the unsupported construct rather than silently succeeding.
+
+#![feature(asm)]
+
+fn unsupp(_x: &mut u8) {
+    unsafe {
+        std::arch::asm!("nop");
+    }
+}
+
+#[kani::proof]
+fn main() {
+    let mut x = 0;
+    let y = 5;
+    if x + y == 5 {
+        unsupp(&mut x);
+    }
+    assert!(x == 0);
+}
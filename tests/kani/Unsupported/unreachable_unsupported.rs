@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --assertion-reach-checks --output-format regular --no-default-checks
+
+// Check that an unsupported construct guarded behind an infeasible branch does
+// not fail the proof: the failure emitted for the construct is only reported
+// when a path reaches it, so an unreachable call verifies.
+
+#![feature(asm)]
+
+fn unsupp(_x: &mut u8) {
+    unsafe {
+        std::arch::asm!("nop");
+    }
+}
+
+#[kani::proof]
+fn main() {
+    let mut x = 0;
+    let y = 5;
+    if x + y == 6 {
+        unsupp(&mut x);
+    }
+    assert!(x == 0);
+}